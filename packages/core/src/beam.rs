@@ -0,0 +1,352 @@
+//! Parallel beam-search selection for `selection_mode = "beam"`.
+//!
+//! Beam search keeps a frontier of the top `beam_width` partial compression
+//! states. Each round, every state is expanded by scoring all candidate
+//! subsequence replacements, and the global top `beam_width` results are
+//! kept for the next round. Scoring that `beam_width x candidates` matrix
+//! is embarrassingly parallel, so individual `(state, candidate)` pairs are
+//! partitioned across a `rayon` worker pool, with per-thread top-k heaps
+//! merged on the main thread.
+
+use std::collections::BinaryHeap;
+
+use crate::config::WasmConfig;
+use crate::types::{BeamState, CandidateExpansion, CompressionConfig};
+
+/// A fixed-capacity top-k heap: keeps only the `k` highest-scoring items
+/// pushed into it, evicting the current minimum once full. Generic so the
+/// selection logic can be unit tested without the real compression types.
+struct TopK<T> {
+    heap: BinaryHeap<std::cmp::Reverse<Scored<T>>>,
+    capacity: usize,
+}
+
+struct Scored<T> {
+    score: u64,
+    item: T,
+}
+
+impl<T> PartialEq for Scored<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
+impl<T> Eq for Scored<T> {}
+impl<T> PartialOrd for Scored<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T> Ord for Scored<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score.cmp(&other.score)
+    }
+}
+
+impl<T> TopK<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, score: u64, item: T) {
+        if self.heap.len() < self.capacity {
+            self.heap.push(std::cmp::Reverse(Scored { score, item }));
+        } else if let Some(std::cmp::Reverse(min)) = self.heap.peek() {
+            if score > min.score {
+                self.heap.pop();
+                self.heap.push(std::cmp::Reverse(Scored { score, item }));
+            }
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for std::cmp::Reverse(scored) in other.heap {
+            self.push(scored.score, scored.item);
+        }
+        self
+    }
+
+    /// Items in descending score order.
+    fn into_sorted_vec(self) -> Vec<T> {
+        let mut scored: Vec<Scored<T>> = self.heap.into_iter().map(|r| r.0).collect();
+        scored.sort_by_key(|s| std::cmp::Reverse(s.score));
+        scored.into_iter().map(|s| s.item).collect()
+    }
+}
+
+/// The `wasm32` build's shared linear memory maximum, declared at link
+/// time via `-C link-arg=--max-memory=...` in `.cargo/config.toml`
+/// (536,870,912 bytes / 512 MB — keep the two in sync). That declaration
+/// is fixed at compile time, so a `WasmConfig.max_memory_mb` budget that
+/// would need more than this can't actually get a bigger shared buffer
+/// by asking; [`BeamSearchPool::new`] checks against it and falls back to
+/// the sequential path rather than over-promising.
+const WASM_LINKED_MAX_MEMORY_BYTES: usize = 512 * 1024 * 1024;
+
+/// The parallel backend, once built. On native targets this is a real
+/// per-run `rayon::ThreadPool`. On `wasm32`, rayon cannot spawn its own
+/// OS threads at all — the only pool available is the process-wide one
+/// `wasm-bindgen-rayon` boots from Web Workers when the JS host calls and
+/// awaits [`crate::init_thread_pool`], so there is nothing to build
+/// per-run; this variant just marks that the global pool is usable.
+enum ParallelBackend {
+    #[cfg(not(target_arch = "wasm32"))]
+    Owned(rayon::ThreadPool),
+    #[cfg(target_arch = "wasm32")]
+    Global,
+}
+
+/// A parallel beam-search backend sized for a run, plus the shared
+/// linear memory ceiling the `wasm32` build declares to back it.
+///
+/// Both are derived from `WasmConfig.max_memory_mb`: more workers need
+/// more shared memory headroom for their scoring scratch space, so the
+/// memory budget caps the worker count rather than using
+/// `WasmConfig.threads` verbatim, and the requested ceiling is checked
+/// against what the linked module can actually back.
+///
+/// Build once per compression run and reuse across every beam-search
+/// round — rebuilding a backend per round is wasteful, and on native
+/// targets ties the thread pool's lifetime to a single run.
+pub struct BeamSearchPool {
+    backend: Option<ParallelBackend>,
+    shared_memory_max_bytes: usize,
+}
+
+/// Each worker needs its own scoring scratch space; budget this many MB
+/// of the shared ceiling per worker when deriving the worker count from
+/// `max_memory_mb`.
+const MIN_MB_PER_WORKER: usize = 16;
+
+/// Headroom above `max_memory_mb` reserved for the non-shared parts of
+/// the module (code, stack, single-threaded scratch buffers) when sizing
+/// the shared linear memory's declared maximum.
+const SHARED_MEMORY_HEADROOM_MB: usize = 32;
+
+impl BeamSearchPool {
+    /// Build the backend for a run, falling back to the sequential path
+    /// (`backend: None`) when `wasm_config.threads <= 1`, the requested
+    /// shared-memory ceiling wouldn't fit the linked module's declared
+    /// maximum, the host lacks `SharedArrayBuffer`, or (native only) the
+    /// pool fails to build. Never panics.
+    pub fn new(wasm_config: &WasmConfig) -> Self {
+        let shared_memory_max_bytes = wasm_config
+            .max_memory_mb
+            .saturating_add(SHARED_MEMORY_HEADROOM_MB)
+            .saturating_mul(1024 * 1024);
+        let threads = effective_thread_count(wasm_config);
+        let fits_linked_memory = shared_memory_max_bytes <= WASM_LINKED_MAX_MEMORY_BYTES;
+
+        let backend = if threads > 1 && fits_linked_memory && threads_supported() {
+            Self::build_backend(threads)
+        } else {
+            None
+        };
+
+        Self {
+            backend,
+            shared_memory_max_bytes,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_backend(threads: usize) -> Option<ParallelBackend> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .ok()
+            .map(ParallelBackend::Owned)
+    }
+
+    /// Thread count on `wasm32` is fixed once at `initThreadPool()` time
+    /// (the JS host's call, awaited before this export is usable), not
+    /// per beam-search round, so there is no count to pass here.
+    #[cfg(target_arch = "wasm32")]
+    fn build_backend(_threads: usize) -> Option<ParallelBackend> {
+        Some(ParallelBackend::Global)
+    }
+
+    /// The shared linear memory maximum this pool was sized against.
+    pub fn shared_memory_max_bytes(&self) -> usize {
+        self.shared_memory_max_bytes
+    }
+}
+
+/// Cap `WasmConfig.threads` by how many workers the memory budget can
+/// actually back with scoring scratch space.
+fn effective_thread_count(wasm_config: &WasmConfig) -> usize {
+    let memory_capped = (wasm_config.max_memory_mb / MIN_MB_PER_WORKER).max(1);
+    wasm_config.threads.min(memory_capped)
+}
+
+/// Run one round of beam search, producing the next beam of at most
+/// `beam_width` states.
+///
+/// Dispatches to the parallel path when `search_pool` holds a usable
+/// backend; otherwise falls back to the single-threaded path.
+pub fn advance_beam(
+    beam: &[BeamState],
+    config: &CompressionConfig,
+    search_pool: &BeamSearchPool,
+) -> Vec<BeamState> {
+    match &search_pool.backend {
+        Some(backend) => advance_beam_parallel(beam, config, backend),
+        None => advance_beam_sequential(beam, config),
+    }
+}
+
+/// Single-threaded fallback: score every candidate expansion of every beam
+/// state in order, keeping a running top-`beam_width` heap.
+fn advance_beam_sequential(beam: &[BeamState], config: &CompressionConfig) -> Vec<BeamState> {
+    let mut top_k = TopK::new(config.beam_width);
+
+    for state in beam {
+        for expansion in state.candidate_expansions(config) {
+            let score = expansion.score();
+            top_k.push(score, expansion);
+        }
+    }
+
+    top_k.into_sorted_vec().into_iter().map(|e| e.into_state()).collect()
+}
+
+/// Parallel path: flatten the `beam_width x candidates` matrix into
+/// individual `(state, candidate)` expansions and partition those across
+/// the worker pool (rather than one state per thread, which starves
+/// workers when the beam is narrow but each state has many candidates),
+/// collecting per-thread top-k heaps and merging them on the main thread.
+fn advance_beam_parallel(
+    beam: &[BeamState],
+    config: &CompressionConfig,
+    backend: &ParallelBackend,
+) -> Vec<BeamState> {
+    use rayon::prelude::*;
+
+    let candidates: Vec<CandidateExpansion> = beam
+        .iter()
+        .flat_map(|state| state.candidate_expansions(config))
+        .collect();
+
+    let score_all = || {
+        candidates
+            .into_par_iter()
+            .fold(
+                || TopK::new(config.beam_width),
+                |mut top_k, expansion| {
+                    let score = expansion.score();
+                    top_k.push(score, expansion);
+                    top_k
+                },
+            )
+            .collect::<Vec<_>>()
+    };
+
+    let per_thread = match backend {
+        #[cfg(not(target_arch = "wasm32"))]
+        ParallelBackend::Owned(pool) => pool.install(score_all),
+        // No local pool to `install` into: this runs on rayon's global
+        // pool, already backed by `wasm-bindgen-rayon`'s Web Workers.
+        #[cfg(target_arch = "wasm32")]
+        ParallelBackend::Global => score_all(),
+    };
+
+    let merged = per_thread
+        .into_iter()
+        .fold(TopK::new(config.beam_width), TopK::merge);
+
+    merged.into_sorted_vec().into_iter().map(|e| e.into_state()).collect()
+}
+
+/// Whether this build was compiled with wasm atomics/shared-memory and the
+/// host actually exposes `SharedArrayBuffer`. Always `true` off `wasm32`
+/// (native builds, e.g. tests, always have real threads).
+fn threads_supported() -> bool {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use wasm_bindgen::{JsCast, JsValue};
+        js_sys::global()
+            .dyn_into::<js_sys::Object>()
+            .map(|g| js_sys::Reflect::has(&g, &JsValue::from_str("SharedArrayBuffer")).unwrap_or(false))
+            .unwrap_or(false)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_k_keeps_only_the_highest_scores() {
+        let mut top_k = TopK::new(3);
+        for score in [5, 1, 9, 3, 7, 2] {
+            top_k.push(score, score);
+        }
+        assert_eq!(top_k.into_sorted_vec(), vec![9, 7, 5]);
+    }
+
+    #[test]
+    fn top_k_merge_respects_capacity() {
+        let mut a = TopK::new(2);
+        a.push(10, 10);
+        a.push(1, 1);
+        let mut b = TopK::new(2);
+        b.push(20, 20);
+        b.push(2, 2);
+
+        let merged = a.merge(b);
+        assert_eq!(merged.into_sorted_vec(), vec![20, 10]);
+    }
+
+    #[test]
+    fn effective_thread_count_is_capped_by_memory_budget() {
+        let mut wasm_config = WasmConfig::new();
+        wasm_config.threads = 8;
+        wasm_config.max_memory_mb = 32; // only room for 2 workers at 16MB each
+        assert_eq!(effective_thread_count(&wasm_config), 2);
+    }
+
+    #[test]
+    fn effective_thread_count_never_goes_below_one() {
+        let mut wasm_config = WasmConfig::new();
+        wasm_config.threads = 4;
+        wasm_config.max_memory_mb = 0;
+        assert_eq!(effective_thread_count(&wasm_config), 1);
+    }
+
+    #[test]
+    fn shared_memory_ceiling_tracks_max_memory_mb() {
+        let mut wasm_config = WasmConfig::new();
+        wasm_config.max_memory_mb = 256;
+        let pool = BeamSearchPool::new(&wasm_config);
+        assert_eq!(
+            pool.shared_memory_max_bytes(),
+            (256 + SHARED_MEMORY_HEADROOM_MB) * 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn falls_back_to_sequential_when_ceiling_exceeds_the_linked_max() {
+        let mut wasm_config = WasmConfig::new();
+        wasm_config.threads = 4;
+        // (600 + 32) MB comfortably exceeds WASM_LINKED_MAX_MEMORY_BYTES (512 MB).
+        wasm_config.max_memory_mb = 600;
+        let pool = BeamSearchPool::new(&wasm_config);
+        assert!(pool.backend.is_none());
+    }
+
+    #[test]
+    fn builds_a_backend_when_the_ceiling_fits() {
+        let mut wasm_config = WasmConfig::new();
+        wasm_config.threads = 2;
+        wasm_config.max_memory_mb = 64;
+        let pool = BeamSearchPool::new(&wasm_config);
+        assert!(pool.backend.is_some());
+    }
+}
@@ -0,0 +1,51 @@
+//! Non-streaming compression entry point.
+//!
+//! Takes a fully materialized input buffer, unlike `compress_stream`, so
+//! `max_memory_mb` can't rely on backpressure between chunks: the minimum
+//! working set is checked once up front, and once the whole input has been
+//! fed the budget is checked again: if it is exceeded, the dictionary stops
+//! growing (rather than backpressuring) before the output is finished.
+
+use wasm_bindgen::prelude::*;
+
+use crate::config::{JsCompressionConfig, WasmConfig};
+use crate::memory::{BudgetAction, MemoryBudget};
+
+/// Compress a fully materialized input buffer.
+///
+/// `js_config` is a plain JS object, marshaled via `serde-wasm-bindgen`
+/// (see [`JsCompressionConfig::from_js_value`]). For inputs at or above
+/// `WasmConfig.streaming_threshold`, prefer `compress_stream` instead so
+/// the memory budget can apply backpressure rather than a single
+/// all-or-nothing check.
+#[wasm_bindgen]
+pub fn compress(
+    input: &[u8],
+    wasm_config: WasmConfig,
+    js_config: JsValue,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let js_config = JsCompressionConfig::from_js_value(js_config)?;
+    let config = js_config.merge_with_defaults()?;
+
+    let mut budget = MemoryBudget::new(wasm_config.max_memory_mb);
+    let mut pipeline = crate::pipeline::Compressor::new(&config, &wasm_config);
+
+    let min_working_set = pipeline.min_working_set_bytes(input.len());
+    if budget.exceeds_minimum(min_working_set) {
+        return Err(crate::memory::budget_exceeded_error(min_working_set, budget.budget_bytes()).into());
+    }
+
+    pipeline.feed(input);
+    budget.track_pattern_table(pipeline.pattern_table_bytes());
+    budget.track_dictionary(pipeline.dictionary_bytes());
+    budget.track_hierarchical(pipeline.hierarchical_bytes());
+
+    if let BudgetAction::StopGrowingDictionary = budget.check(0, false) {
+        pipeline.stop_growing_dictionary();
+    }
+
+    let output = pipeline
+        .finish()
+        .map_err(|e| JsValue::from_str(&format!("compression error: {e}")))?;
+    Ok(js_sys::Uint8Array::from(output.as_slice()))
+}
@@ -5,6 +5,9 @@
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+/// Selection modes accepted by `JsCompressionConfig.selection_mode`.
+const VALID_SELECTION_MODES: [&str; 3] = ["greedy", "optimal", "beam"];
+
 /// WASM-specific configuration for memory and performance tuning.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[wasm_bindgen]
@@ -15,6 +18,10 @@ pub struct WasmConfig {
     pub chunk_size: usize,
     /// Enable streaming for inputs above threshold (default: 50000)
     pub streaming_threshold: usize,
+    /// Worker threads to use for parallel beam search (default: 1, meaning
+    /// single-threaded). Ignored outside of `selection_mode = "beam"` or
+    /// when the host has no `SharedArrayBuffer` support.
+    pub threads: usize,
 }
 
 impl Default for WasmConfig {
@@ -23,6 +30,7 @@ impl Default for WasmConfig {
             max_memory_mb: 256,
             chunk_size: 32768,
             streaming_threshold: 50000,
+            threads: 1,
         }
     }
 }
@@ -36,6 +44,10 @@ impl WasmConfig {
 }
 
 /// Configuration passed from JavaScript for compression.
+///
+/// Marshaled straight off a plain JS object via `serde-wasm-bindgen`
+/// (see [`JsCompressionConfig::from_js_value`]), so there is no
+/// intermediate `JSON.stringify`/`parse` round trip.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct JsCompressionConfig {
     /// Minimum pattern length
@@ -58,11 +70,55 @@ pub struct JsCompressionConfig {
     pub dict_end_token: Option<u32>,
     /// Next meta-token ID to use
     pub next_meta_token: Option<u32>,
+    /// A previously exported meta-token dictionary (see
+    /// [`crate::dictionary_io::export_dictionary`]) to reuse instead of
+    /// learning patterns from scratch.
+    pub preset_dictionary: Option<Vec<u8>>,
 }
 
 impl JsCompressionConfig {
-    /// Merge with default compression config.
-    pub fn merge_with_defaults(&self) -> crate::types::CompressionConfig {
+    /// Deserialize directly from a JS object passed across the wasm
+    /// boundary, without going through a JSON string.
+    pub fn from_js_value(value: JsValue) -> Result<Self, JsError> {
+        serde_wasm_bindgen::from_value(value)
+            .map_err(|e| JsError::new(&format!("invalid compression config: {e}")))
+    }
+
+    /// Check the fields that can't be caught by the type system alone:
+    /// an unrecognized `selection_mode`, an inverted subsequence length
+    /// range, or a zero `beam_width` while beam mode is selected.
+    ///
+    /// Returns a plain `String` rather than `JsError` so this can be unit
+    /// tested on native targets — constructing a `JsError` calls into a
+    /// `wasm-bindgen` import that panics off `wasm32`. Callers across the
+    /// wasm boundary (e.g. [`Self::merge_with_defaults`]) convert it.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref mode) = self.selection_mode {
+            if !VALID_SELECTION_MODES.contains(&mode.as_str()) {
+                return Err(format!(
+                    "invalid selection_mode {mode:?}, expected one of {VALID_SELECTION_MODES:?}"
+                ));
+            }
+            if mode == "beam" && self.beam_width == Some(0) {
+                return Err(
+                    "beam_width must be greater than 0 when selection_mode is \"beam\"".to_string(),
+                );
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_subsequence_length, self.max_subsequence_length) {
+            if min > max {
+                return Err(format!(
+                    "min_subsequence_length ({min}) must not exceed max_subsequence_length ({max})"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate, then merge with the default compression config.
+    pub fn merge_with_defaults(&self) -> Result<crate::types::CompressionConfig, JsError> {
+        self.validate().map_err(|e| JsError::new(&e))?;
+
         let mut config = crate::types::CompressionConfig::default();
 
         if let Some(v) = self.min_subsequence_length {
@@ -92,7 +148,71 @@ impl JsCompressionConfig {
         if let Some(v) = self.dict_end_token {
             config.dict_end_token = v;
         }
+        if let Some(ref bytes) = self.preset_dictionary {
+            let dictionary = crate::dictionary_io::import_dictionary(bytes, &config)?;
+            config.preset_dictionary = Some(dictionary);
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> JsCompressionConfig {
+        JsCompressionConfig::default()
+    }
+
+    #[test]
+    fn accepts_known_selection_modes() {
+        for mode in VALID_SELECTION_MODES {
+            let mut config = base();
+            config.selection_mode = Some(mode.to_string());
+            if mode == "beam" {
+                config.beam_width = Some(4);
+            }
+            assert!(config.validate().is_ok(), "{mode} should be valid");
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_selection_mode() {
+        let mut config = base();
+        config.selection_mode = Some("beem".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_zero_beam_width_in_beam_mode() {
+        let mut config = base();
+        config.selection_mode = Some("beam".to_string());
+        config.beam_width = Some(0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn zero_beam_width_is_fine_outside_beam_mode() {
+        let mut config = base();
+        config.selection_mode = Some("greedy".to_string());
+        config.beam_width = Some(0);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_inverted_subsequence_length_range() {
+        let mut config = base();
+        config.min_subsequence_length = Some(10);
+        config.max_subsequence_length = Some(5);
+        assert!(config.validate().is_err());
+    }
 
-        config
+    #[test]
+    fn accepts_equal_subsequence_length_bounds() {
+        let mut config = base();
+        config.min_subsequence_length = Some(5);
+        config.max_subsequence_length = Some(5);
+        assert!(config.validate().is_ok());
     }
 }
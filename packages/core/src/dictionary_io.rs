@@ -0,0 +1,171 @@
+//! Export and import of trained meta-token dictionaries.
+//!
+//! A dictionary trained on a representative corpus can be reused to
+//! compress many small, similar inputs without re-learning patterns on
+//! every call. This module (de)serializes the dictionary built from
+//! `dict_start_token`/`dict_end_token`/`next_meta_token` to and from a
+//! standalone, versioned binary artifact, and lets the decompressor
+//! accept the same artifact so compressed payloads can omit the inline
+//! dictionary when both sides share it.
+
+use wasm_bindgen::prelude::*;
+
+use crate::types::{CompressionConfig, MetaTokenDictionary};
+
+/// Binary format version. Bumped whenever the artifact layout changes so
+/// old exports fail fast on import rather than silently misparsing.
+const ARTIFACT_VERSION: u8 = 1;
+const MAGIC: [u8; 4] = *b"DLTD";
+/// MAGIC(4) + version(1) + dict_start_token(4) + next_meta_token(4).
+const HEADER_LEN: usize = 13;
+
+/// Encode the artifact header shared by export and both import paths.
+fn encode_header(dict_start_token: u32, next_meta_token: u32) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[0..4].copy_from_slice(&MAGIC);
+    header[4] = ARTIFACT_VERSION;
+    header[5..9].copy_from_slice(&dict_start_token.to_le_bytes());
+    header[9..13].copy_from_slice(&next_meta_token.to_le_bytes());
+    header
+}
+
+/// Parse and validate the artifact header, returning the token range and
+/// the remaining (dictionary-encoded) payload.
+///
+/// Returns a plain `String` rather than `JsError` so this can be unit
+/// tested on native targets — constructing a `JsError` calls into a
+/// `wasm-bindgen` import that panics off `wasm32`. Callers across the
+/// wasm boundary ([`import_dictionary`], [`import_dictionary_for_decompression`])
+/// convert it.
+fn parse_header(bytes: &[u8]) -> Result<(u32, u32, &[u8]), String> {
+    if bytes.len() < HEADER_LEN || bytes[0..4] != MAGIC {
+        return Err("dictionary artifact is not valid".to_string());
+    }
+    let version = bytes[4];
+    if version != ARTIFACT_VERSION {
+        return Err(format!(
+            "dictionary artifact version {version} is not supported (expected {ARTIFACT_VERSION})"
+        ));
+    }
+
+    let dict_start_token = u32::from_le_bytes(bytes[5..9].try_into().unwrap());
+    let next_meta_token = u32::from_le_bytes(bytes[9..13].try_into().unwrap());
+    Ok((dict_start_token, next_meta_token, &bytes[HEADER_LEN..]))
+}
+
+/// Export the dictionary learned during a compression run as a
+/// standalone, versioned artifact that can be persisted and later passed
+/// back in as `JsCompressionConfig.preset_dictionary` (compression side)
+/// or `decompress`'s `preset_dictionary` argument (decompression side).
+#[wasm_bindgen]
+pub fn export_dictionary(dictionary: &MetaTokenDictionary) -> js_sys::Uint8Array {
+    let mut out = Vec::with_capacity(HEADER_LEN + dictionary.encoded_len());
+    out.extend_from_slice(&encode_header(
+        dictionary.dict_start_token(),
+        dictionary.next_meta_token(),
+    ));
+    dictionary.encode_into(&mut out);
+    js_sys::Uint8Array::from(out.as_slice())
+}
+
+/// Parse and validate a dictionary artifact previously produced by
+/// [`export_dictionary`] for reuse on the compression side, checking that
+/// it was trained with this config's `dict_start_token` and that its
+/// `next_meta_token` does not exceed the config's `dict_end_token`. The
+/// config's own `next_meta_token` is not compared.
+pub fn import_dictionary(bytes: &[u8], config: &CompressionConfig) -> Result<MetaTokenDictionary, JsError> {
+    import_dictionary_inner(bytes, config).map_err(|e| JsError::new(&e))
+}
+
+fn import_dictionary_inner(bytes: &[u8], config: &CompressionConfig) -> Result<MetaTokenDictionary, String> {
+    let (dict_start_token, next_meta_token, payload) = parse_header(bytes)?;
+
+    if dict_start_token != config.dict_start_token {
+        return Err(format!(
+            "preset_dictionary was trained with dict_start_token {dict_start_token}, \
+             but this config uses {}",
+            config.dict_start_token
+        ));
+    }
+    if next_meta_token > config.dict_end_token {
+        return Err(format!(
+            "preset_dictionary's next_meta_token ({next_meta_token}) would collide with \
+             dict_end_token ({}); widen the token range or retrain the dictionary",
+            config.dict_end_token
+        ));
+    }
+
+    MetaTokenDictionary::decode(payload, dict_start_token, next_meta_token)
+        .map_err(|e| format!("failed to decode preset_dictionary: {e}"))
+}
+
+/// Parse a dictionary artifact for reuse on the decompression side. Unlike
+/// [`import_dictionary`], there is no compression config to collide
+/// against: the decompressor only looks tokens up, it never mints new
+/// ones, so the artifact's own token range is authoritative.
+pub fn import_dictionary_for_decompression(bytes: &[u8]) -> Result<MetaTokenDictionary, JsError> {
+    import_dictionary_for_decompression_inner(bytes).map_err(|e| JsError::new(&e))
+}
+
+fn import_dictionary_for_decompression_inner(bytes: &[u8]) -> Result<MetaTokenDictionary, String> {
+    let (dict_start_token, next_meta_token, payload) = parse_header(bytes)?;
+    MetaTokenDictionary::decode(payload, dict_start_token, next_meta_token)
+        .map_err(|e| format!("failed to decode preset_dictionary: {e}"))
+}
+
+/// Decompress a payload, optionally supplying a dictionary artifact
+/// shared out of band (via [`export_dictionary`]) for payloads that were
+/// compressed with `preset_dictionary` and so omit the inline dictionary.
+#[wasm_bindgen]
+pub fn decompress(payload: &[u8], preset_dictionary: Option<Vec<u8>>) -> Result<js_sys::Uint8Array, JsValue> {
+    let dictionary = preset_dictionary
+        .map(|bytes| import_dictionary_for_decompression(&bytes))
+        .transpose()?;
+
+    let output = crate::pipeline::Decompressor::new(dictionary)
+        .decompress(payload)
+        .map_err(|e| JsValue::from_str(&format!("decompression error: {e}")))?;
+    Ok(js_sys::Uint8Array::from(output.as_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_round_trips() {
+        let header = encode_header(100, 150);
+        let (dict_start_token, next_meta_token, payload) = parse_header(&header).unwrap();
+        assert_eq!(dict_start_token, 100);
+        assert_eq!(next_meta_token, 150);
+        assert!(payload.is_empty());
+    }
+
+    #[test]
+    fn header_preserves_trailing_payload() {
+        let mut bytes = encode_header(1, 2).to_vec();
+        bytes.extend_from_slice(&[0xAA, 0xBB, 0xCC]);
+        let (_, _, payload) = parse_header(&bytes).unwrap();
+        assert_eq!(payload, &[0xAA, 0xBB, 0xCC]);
+    }
+
+    #[test]
+    fn rejects_wrong_magic() {
+        let mut bytes = encode_header(1, 2).to_vec();
+        bytes[0] = b'X';
+        assert!(parse_header(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let mut bytes = encode_header(1, 2).to_vec();
+        bytes[4] = ARTIFACT_VERSION + 1;
+        assert!(parse_header(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let bytes = encode_header(1, 2);
+        assert!(parse_header(&bytes[..HEADER_LEN - 1]).is_err());
+    }
+}
@@ -0,0 +1,19 @@
+//! WASM-facing compression crate.
+
+mod types;
+mod pipeline;
+
+pub mod beam;
+pub mod compress;
+pub mod config;
+pub mod dictionary_io;
+pub mod memory;
+pub mod streaming;
+
+/// JS-callable thread-pool bootstrapper generated by `wasm-bindgen-rayon`.
+/// The host must `await initThreadPool(navigator.hardwareConcurrency)`
+/// once, before calling any export that uses the parallel beam-search
+/// path in [`beam`] — wasm32 has no ability to spawn its own threads, so
+/// rayon's global pool is built from Web Workers on the JS side instead.
+#[cfg(target_arch = "wasm32")]
+pub use wasm_bindgen_rayon::init_thread_pool;
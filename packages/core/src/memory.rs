@@ -0,0 +1,142 @@
+//! Allocation accounting for `WasmConfig.max_memory_mb`.
+//!
+//! Tracks the approximate live bytes of the pattern tables, meta-token
+//! dictionary, and hierarchical levels so a compression run can stop
+//! growing its working set before it blows past the configured budget,
+//! instead of hitting an opaque out-of-memory trap.
+
+use wasm_bindgen::prelude::*;
+
+/// Running estimate of live bytes across the structures a compression run
+/// allocates, checked against a fixed budget derived from
+/// `WasmConfig.max_memory_mb`.
+pub struct MemoryBudget {
+    budget_bytes: usize,
+    pattern_table_bytes: usize,
+    dictionary_bytes: usize,
+    hierarchical_bytes: usize,
+}
+
+/// What to do once a compression run's estimated live bytes would exceed
+/// the budget.
+pub enum BudgetAction {
+    /// Keep going as normal; still under budget.
+    Continue,
+    /// Stop growing the dictionary and emit what has been found so far.
+    StopGrowingDictionary,
+    /// In streaming mode, flush completed segments before pulling the next
+    /// chunk.
+    FlushSegments,
+}
+
+impl MemoryBudget {
+    /// Build a budget from `WasmConfig.max_memory_mb`.
+    pub fn new(max_memory_mb: usize) -> Self {
+        Self {
+            budget_bytes: max_memory_mb.saturating_mul(1024 * 1024),
+            pattern_table_bytes: 0,
+            dictionary_bytes: 0,
+            hierarchical_bytes: 0,
+        }
+    }
+
+    /// Record a change in the pattern table's estimated size.
+    pub fn track_pattern_table(&mut self, bytes: usize) {
+        self.pattern_table_bytes = bytes;
+    }
+
+    /// Record a change in the meta-token dictionary's estimated size.
+    pub fn track_dictionary(&mut self, bytes: usize) {
+        self.dictionary_bytes = bytes;
+    }
+
+    /// Record a change in the hierarchical levels' estimated size.
+    pub fn track_hierarchical(&mut self, bytes: usize) {
+        self.hierarchical_bytes = bytes;
+    }
+
+    /// Current estimated live bytes across all tracked structures.
+    pub fn estimated_live_bytes(&self) -> usize {
+        self.pattern_table_bytes + self.dictionary_bytes + self.hierarchical_bytes
+    }
+
+    /// Decide what a caller about to grow the dictionary further or pull
+    /// another streaming chunk should do, given the current estimate plus
+    /// `additional_bytes` it is about to allocate.
+    pub fn check(&self, additional_bytes: usize, streaming: bool) -> BudgetAction {
+        if self.estimated_live_bytes() + additional_bytes <= self.budget_bytes {
+            return BudgetAction::Continue;
+        }
+        if streaming {
+            BudgetAction::FlushSegments
+        } else {
+            BudgetAction::StopGrowingDictionary
+        }
+    }
+
+    /// Whether even the minimum working set (`min_working_set_bytes`)
+    /// exceeds the cap, meaning the run cannot proceed at all.
+    pub fn exceeds_minimum(&self, min_working_set_bytes: usize) -> bool {
+        min_working_set_bytes > self.budget_bytes
+    }
+
+    /// The configured budget in bytes, for error messages.
+    pub fn budget_bytes(&self) -> usize {
+        self.budget_bytes
+    }
+}
+
+/// Build the `JsError` returned when even the minimum working set exceeds
+/// `max_memory_mb`, carrying the peak-bytes estimate so callers can size
+/// inputs or raise the limit deterministically.
+pub fn budget_exceeded_error(min_working_set_bytes: usize, budget_bytes: usize) -> JsError {
+    JsError::new(&format!(
+        "minimum working set ({min_working_set_bytes} bytes) exceeds max_memory_mb budget \
+         ({budget_bytes} bytes); raise max_memory_mb or reduce input size"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continues_under_budget() {
+        let mut budget = MemoryBudget::new(1); // 1 MB
+        budget.track_pattern_table(100);
+        budget.track_dictionary(100);
+        budget.track_hierarchical(100);
+        assert!(matches!(budget.check(100, false), BudgetAction::Continue));
+        assert!(matches!(budget.check(100, true), BudgetAction::Continue));
+    }
+
+    #[test]
+    fn stops_growing_dictionary_over_budget_non_streaming() {
+        let mut budget = MemoryBudget::new(1); // 1 MB = 1_048_576 bytes
+        budget.track_dictionary(1_048_576);
+        assert!(matches!(budget.check(1, false), BudgetAction::StopGrowingDictionary));
+    }
+
+    #[test]
+    fn flushes_segments_over_budget_streaming() {
+        let mut budget = MemoryBudget::new(1);
+        budget.track_dictionary(1_048_576);
+        assert!(matches!(budget.check(1, true), BudgetAction::FlushSegments));
+    }
+
+    #[test]
+    fn tracks_all_three_structures_in_the_live_estimate() {
+        let mut budget = MemoryBudget::new(256);
+        budget.track_pattern_table(10);
+        budget.track_dictionary(20);
+        budget.track_hierarchical(30);
+        assert_eq!(budget.estimated_live_bytes(), 60);
+    }
+
+    #[test]
+    fn exceeds_minimum_when_working_set_alone_is_over_budget() {
+        let budget = MemoryBudget::new(1); // 1_048_576 bytes
+        assert!(!budget.exceeds_minimum(1_048_576));
+        assert!(budget.exceeds_minimum(1_048_577));
+    }
+}
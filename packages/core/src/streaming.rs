@@ -0,0 +1,291 @@
+//! Streaming input support for WASM compression entry points.
+//!
+//! Lets JavaScript callers feed chunks incrementally instead of materializing
+//! the whole input in memory. Each chunk reaches the pipeline at the size the
+//! source yields it; `WasmConfig.chunk_size` does not split or cap chunks.
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::config::{JsCompressionConfig, WasmConfig};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Source of input chunks for streaming compression.
+///
+/// Either a native JS `ReadableStreamDefaultReader` or a plain
+/// `next_chunk() -> Promise<Option<Uint8Array>>` callback, whichever the
+/// caller has on hand.
+enum ChunkSource {
+    Reader(web_sys::ReadableStreamDefaultReader),
+    Callback(js_sys::Function),
+}
+
+impl ChunkSource {
+    async fn next(&self) -> Result<Option<Vec<u8>>, JsValue> {
+        let (value, done) = match self {
+            ChunkSource::Reader(reader) => {
+                let result = JsFuture::from(reader.read()).await?;
+                let done = js_sys::Reflect::get(&result, &JsValue::from_str("done"))?
+                    .as_bool()
+                    .unwrap_or(false);
+                let value = js_sys::Reflect::get(&result, &JsValue::from_str("value"))?;
+                (value, done)
+            }
+            ChunkSource::Callback(callback) => {
+                let result = callback.call0(&JsValue::NULL)?;
+                let promise: js_sys::Promise = result.dyn_into()?;
+                let value = JsFuture::from(promise).await?;
+                let done = value.is_undefined() || value.is_null();
+                (value, done)
+            }
+        };
+
+        if done {
+            return Ok(None);
+        }
+        let bytes: js_sys::Uint8Array = value.dyn_into()?;
+        Ok(Some(bytes.to_vec()))
+    }
+}
+
+/// Sniffs a byte stream for the gzip magic (`0x1f 0x8b`) and transparently
+/// inflates it if present. Pure and synchronous so it can be unit tested
+/// without a JS host; [`GzipSniffingSource`] is the async adapter that
+/// feeds it from a [`ChunkSource`].
+///
+/// Buffers fed bytes until at least two have arrived before deciding,
+/// so a 1-byte first chunk doesn't misdetect raw input.
+struct GzipInflateState {
+    inflate: Option<flate2::write::GzDecoder<Vec<u8>>>,
+    sniff_buffer: Vec<u8>,
+    sniffed: bool,
+}
+
+impl GzipInflateState {
+    fn new() -> Self {
+        Self {
+            inflate: None,
+            sniff_buffer: Vec::new(),
+            sniffed: false,
+        }
+    }
+
+    fn feed(&mut self, chunk: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        if !self.sniffed {
+            self.sniff_buffer.extend_from_slice(chunk);
+            if self.sniff_buffer.len() < 2 {
+                return Ok(None);
+            }
+            self.sniffed = true;
+            if self.sniff_buffer[..2] == GZIP_MAGIC {
+                self.inflate = Some(flate2::write::GzDecoder::new(Vec::new()));
+            }
+            let buffered = std::mem::take(&mut self.sniff_buffer);
+            return self.process(&buffered).map(Some);
+        }
+
+        self.process(chunk).map(Some)
+    }
+
+    fn process(&mut self, chunk: &[u8]) -> Result<Vec<u8>, String> {
+        use std::io::Write;
+
+        match self.inflate.as_mut() {
+            Some(decoder) => {
+                decoder
+                    .write_all(chunk)
+                    .map_err(|e| format!("gzip inflate error: {e}"))?;
+                decoder.flush().ok();
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            None => Ok(chunk.to_vec()),
+        }
+    }
+
+    /// Called once the underlying source is exhausted: flush a sniff
+    /// buffer that never reached two bytes, or finalize the inflate
+    /// decoder so its CRC/ISIZE footer is checked and any output still
+    /// held internally is returned.
+    fn finish(&mut self) -> Result<Option<Vec<u8>>, String> {
+        if !self.sniffed && !self.sniff_buffer.is_empty() {
+            self.sniffed = true;
+            return Ok(Some(std::mem::take(&mut self.sniff_buffer)));
+        }
+
+        match self.inflate.take() {
+            Some(decoder) => {
+                let tail = decoder
+                    .finish()
+                    .map_err(|e| format!("gzip footer validation failed: {e}"))?;
+                Ok(if tail.is_empty() { None } else { Some(tail) })
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Wraps a `ChunkSource`, transparently inflating gzip-compressed input via
+/// a [`GzipInflateState`].
+struct GzipSniffingSource {
+    inner: ChunkSource,
+    state: GzipInflateState,
+}
+
+impl GzipSniffingSource {
+    fn new(inner: ChunkSource) -> Self {
+        Self {
+            inner,
+            state: GzipInflateState::new(),
+        }
+    }
+
+    async fn next(&mut self) -> Result<Option<Vec<u8>>, JsValue> {
+        loop {
+            let Some(chunk) = self.inner.next().await? else {
+                return self.state.finish().map_err(|e| JsValue::from_str(&e));
+            };
+
+            if let Some(out) = self.state.feed(&chunk).map_err(|e| JsValue::from_str(&e))? {
+                return Ok(Some(out));
+            }
+            // Not enough bytes yet to sniff gzip vs. raw; keep pulling.
+        }
+    }
+}
+
+/// Compress a stream of input chunks, feeding each chunk to the pipeline
+/// whole, at the size the source (or gzip inflation) yields it; chunks are
+/// not split to `chunk_size`. Once `streaming_threshold` bytes have been
+/// fed, the memory budget is checked after every chunk and
+/// `drain_ready(chunk_size)` is called.
+///
+/// `source` must be either a `ReadableStreamDefaultReader` or a function
+/// with the signature `() -> Promise<Uint8Array | undefined>`; the latter
+/// should resolve to `undefined` once exhausted. Gzip input is detected and
+/// inflated automatically. `js_config` is a plain JS object, marshaled via
+/// `serde-wasm-bindgen` rather than an intermediate JSON string.
+#[wasm_bindgen]
+pub async fn compress_stream(
+    source: JsValue,
+    wasm_config: WasmConfig,
+    js_config: JsValue,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let js_config = JsCompressionConfig::from_js_value(js_config)?;
+    let config = js_config.merge_with_defaults()?;
+
+    let chunk_source = if let Ok(reader) = source.clone().dyn_into::<web_sys::ReadableStreamDefaultReader>() {
+        ChunkSource::Reader(reader)
+    } else if let Ok(callback) = source.dyn_into::<js_sys::Function>() {
+        ChunkSource::Callback(callback)
+    } else {
+        return Err(JsValue::from_str(
+            "source must be a ReadableStreamDefaultReader or a next_chunk() callback",
+        ));
+    };
+
+    let mut source = GzipSniffingSource::new(chunk_source);
+    let mut pipeline = crate::pipeline::IncrementalCompressor::new(&config, &wasm_config);
+    let mut budget = crate::memory::MemoryBudget::new(wasm_config.max_memory_mb);
+
+    let min_working_set = pipeline.min_working_set_bytes(wasm_config.chunk_size);
+    if budget.exceeds_minimum(min_working_set) {
+        return Err(crate::memory::budget_exceeded_error(min_working_set, budget.budget_bytes()).into());
+    }
+
+    let mut buffered = 0usize;
+
+    while let Some(chunk) = source.next().await? {
+        buffered += chunk.len();
+        budget.track_pattern_table(pipeline.pattern_table_bytes());
+        budget.track_dictionary(pipeline.dictionary_bytes());
+        budget.track_hierarchical(pipeline.hierarchical_bytes());
+        pipeline.feed(&chunk);
+
+        if buffered >= wasm_config.streaming_threshold {
+            // `streaming: true` means `check` only ever returns `Continue` or
+            // `FlushSegments` here; `StopGrowingDictionary` is the
+            // non-streaming `compress()` path's concern.
+            if let crate::memory::BudgetAction::FlushSegments = budget.check(chunk.len(), true) {
+                pipeline.flush_completed_segments();
+            }
+            pipeline.drain_ready(wasm_config.chunk_size);
+        }
+    }
+
+    let output = pipeline
+        .finish()
+        .map_err(|e| JsValue::from_str(&format!("compression error: {e}")))?;
+    Ok(js_sys::Uint8Array::from(output.as_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn gzip_bytes(raw: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(raw).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn passes_through_raw_input_unchanged() {
+        let mut state = GzipInflateState::new();
+        let mut out = Vec::new();
+        for chunk in [b"hello ".as_slice(), b"world".as_slice()] {
+            if let Some(bytes) = state.feed(chunk).unwrap() {
+                out.extend(bytes);
+            }
+        }
+        if let Some(bytes) = state.finish().unwrap() {
+            out.extend(bytes);
+        }
+        assert_eq!(out, b"hello world");
+    }
+
+    #[test]
+    fn inflates_gzip_input_fed_as_one_chunk() {
+        let compressed = gzip_bytes(b"the quick brown fox");
+        let mut state = GzipInflateState::new();
+        let mut out = Vec::new();
+        if let Some(bytes) = state.feed(&compressed).unwrap() {
+            out.extend(bytes);
+        }
+        if let Some(bytes) = state.finish().unwrap() {
+            out.extend(bytes);
+        }
+        assert_eq!(out, b"the quick brown fox");
+    }
+
+    #[test]
+    fn sniffs_gzip_magic_split_across_a_one_byte_first_chunk() {
+        let compressed = gzip_bytes(b"split across chunks");
+        let mut state = GzipInflateState::new();
+        let mut out = Vec::new();
+
+        // Feed the first byte alone: not enough to sniff yet.
+        assert_eq!(state.feed(&compressed[..1]).unwrap(), None);
+
+        for chunk in compressed[1..].chunks(3) {
+            if let Some(bytes) = state.feed(chunk).unwrap() {
+                out.extend(bytes);
+            }
+        }
+        if let Some(bytes) = state.finish().unwrap() {
+            out.extend(bytes);
+        }
+        assert_eq!(out, b"split across chunks");
+    }
+
+    #[test]
+    fn rejects_truncated_gzip_input() {
+        let mut compressed = gzip_bytes(b"will be truncated");
+        compressed.truncate(compressed.len() - 8); // drop the CRC/ISIZE footer
+        let mut state = GzipInflateState::new();
+        state.feed(&compressed).unwrap();
+        assert!(state.finish().is_err());
+    }
+}